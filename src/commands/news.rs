@@ -0,0 +1,154 @@
+use poise::serenity_prelude::CreateEmbed;
+
+use crate::{controllers::database, Context, Error};
+
+const DESCRIPTION_MAX_LEN: usize = 300;
+
+/// Strip HTML tags from `input`, leaving plain text behind.
+fn strip_html(input: &str) -> String {
+    let mut text = String::with_capacity(input.len());
+    let mut in_tag = false;
+
+    for c in input.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Truncate `input` to at most `max_len` chars, appending an ellipsis if
+/// anything was cut.
+fn truncate(input: &str, max_len: usize) -> String {
+    if input.chars().count() <= max_len {
+        return input.to_string();
+    }
+
+    let mut truncated: String = input.chars().take(max_len).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Build the embed used to announce a new RSS item in Discord: title,
+/// sanitized/truncated description, the article URL, publish date in the
+/// footer, an image when the item carries one, and the feed's configured
+/// `accent_color`.
+pub fn build_story_embed(item: &rss::Item, accent_color: u32) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+
+    if let Some(title) = item.title() {
+        embed.title(title);
+    }
+
+    let raw_description = item.content().or_else(|| item.description()).unwrap_or("");
+    let description = truncate(&strip_html(raw_description), DESCRIPTION_MAX_LEN);
+    if !description.is_empty() {
+        embed.description(description);
+    }
+
+    if let Some(link) = item.link() {
+        embed.url(link);
+    }
+
+    if let Some(pub_date) = item.pub_date() {
+        embed.footer(|footer| footer.text(pub_date));
+    }
+
+    if let Some(enclosure) = item.enclosure() {
+        if enclosure.mime_type().starts_with("image/") {
+            embed.image(enclosure.url());
+        }
+    }
+
+    embed.color(accent_color);
+
+    embed
+}
+
+/// Manage this channel's feed subscriptions.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("feed_subscribe", "feed_unsubscribe", "feed_list")
+)]
+pub async fn feed(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Subscribe this channel to a feed.
+#[poise::command(slash_command, prefix_command, rename = "subscribe")]
+pub async fn feed_subscribe(
+    ctx: Context<'_>,
+    #[description = "RSS feed URL"] url: String,
+) -> Result<(), Error> {
+    database::subscribe(&ctx.data().db, ctx.channel_id().0, &url).await?;
+    ctx.say(format!("Subscribed this channel to <{}>", url)).await?;
+
+    Ok(())
+}
+
+/// Unsubscribe this channel from a feed.
+#[poise::command(slash_command, prefix_command, rename = "unsubscribe")]
+pub async fn feed_unsubscribe(
+    ctx: Context<'_>,
+    #[description = "RSS feed URL"] url: String,
+) -> Result<(), Error> {
+    database::unsubscribe(&ctx.data().db, ctx.channel_id().0, &url).await?;
+    ctx.say(format!("Unsubscribed this channel from <{}>", url))
+        .await?;
+
+    Ok(())
+}
+
+/// List the feeds this channel is subscribed to.
+#[poise::command(slash_command, prefix_command, rename = "list")]
+pub async fn feed_list(ctx: Context<'_>) -> Result<(), Error> {
+    let feeds = database::list_subscriptions(&ctx.data().db, ctx.channel_id().0).await?;
+
+    if feeds.is_empty() {
+        ctx.say("This channel has no feed subscriptions.").await?;
+        return Ok(());
+    }
+
+    ctx.say(feeds.join("\n")).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_html_removes_tags_and_collapses_whitespace() {
+        let input = "<p>Hello <b>world</b></p>\n<br/>Goodbye";
+        assert_eq!(strip_html(input), "Hello world Goodbye");
+    }
+
+    #[test]
+    fn strip_html_leaves_entities_undecoded() {
+        // Decoding HTML entities is out of scope; only tags are stripped.
+        assert_eq!(strip_html("Tom &amp; Jerry"), "Tom &amp; Jerry");
+    }
+
+    #[test]
+    fn strip_html_handles_empty_input() {
+        assert_eq!(strip_html(""), "");
+    }
+
+    #[test]
+    fn truncate_leaves_short_input_untouched() {
+        assert_eq!(truncate("short", 10), "short");
+    }
+
+    #[test]
+    fn truncate_counts_chars_not_bytes_for_multibyte_input() {
+        let input = "café";
+        assert_eq!(truncate(input, 4), "café");
+        assert_eq!(truncate(input, 3), "caf…");
+    }
+}
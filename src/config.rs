@@ -0,0 +1,79 @@
+use once_cell::sync::Lazy;
+use poise::serenity_prelude::ChannelId;
+use serde::Deserialize;
+use std::{env::var, fs, io::ErrorKind};
+
+/// Default cadence for a feed discovered at runtime via `/feed subscribe`,
+/// which has no registry entry to read a cadence from.
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
+
+/// Default embed accent color for a feed that doesn't set `accent_color`, and
+/// for feeds discovered at runtime via `/feed subscribe`.
+pub const DEFAULT_ACCENT_COLOR: u32 = 0x00AEEF;
+
+/// One publication this bot polls: where to fetch it, where to post it, how
+/// often to check for new stories, and what color to accent its embeds with.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedConfig {
+    pub url: String,
+    pub channel_id: u64,
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default = "default_accent_color")]
+    pub accent_color: u32,
+}
+
+impl FeedConfig {
+    pub fn channel(&self) -> ChannelId {
+        ChannelId(self.channel_id)
+    }
+}
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_POLL_INTERVAL_SECS
+}
+
+fn default_accent_color() -> u32 {
+    DEFAULT_ACCENT_COLOR
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FeedsFile {
+    #[serde(rename = "feed", default)]
+    feeds: Vec<FeedConfig>,
+}
+
+/// The feed registry, loaded once from the TOML file at `FEEDS_CONFIG_PATH`
+/// (default `feeds.toml`) so new publications can be covered without a
+/// recompile. A missing file is treated as an empty registry rather than a
+/// startup failure, since a deployment may rely purely on `/feed subscribe`.
+///
+/// ```toml
+/// [[feed]]
+/// url = "https://www.dlnews.com/arc/outboundfeeds/rss/"
+/// channel_id = 1143749967706603602
+/// interval_secs = 60
+/// accent_color = 0x00AEEF
+/// ```
+pub static FEEDS: Lazy<Vec<FeedConfig>> = Lazy::new(|| {
+    let path = var("FEEDS_CONFIG_PATH").unwrap_or_else(|_| "feeds.toml".into());
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Vec::new(),
+        Err(e) => panic!("Failed to read feed config at `{}`: {}", path, e),
+    };
+
+    toml::from_str::<FeedsFile>(&contents)
+        .unwrap_or_else(|e| panic!("Failed to parse feed config at `{}`: {}", path, e))
+        .feeds
+});
+
+/// Channel that receives "bot ready" and error notifications, configured via
+/// `NOTIFY_CHANNEL_ID`. Optional: operators who'd rather tail logs can leave
+/// it unset.
+pub static NOTIFY_CHANNEL_ID: Lazy<Option<ChannelId>> = Lazy::new(|| {
+    var("NOTIFY_CHANNEL_ID")
+        .ok()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(ChannelId)
+});
@@ -0,0 +1,228 @@
+use bb8::Pool;
+use bb8_postgres::PostgresConnectionManager;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+use tokio_postgres::NoTls;
+
+use crate::Error;
+
+pub type DbPool = Pool<PostgresConnectionManager<NoTls>>;
+
+/// Build a connection pool from a `postgres://` connection string and make sure
+/// the `posted_articles` table exists.
+pub async fn connect(database_url: &str) -> Result<DbPool, Error> {
+    let config = database_url.parse()?;
+    let manager = PostgresConnectionManager::new(config, NoTls);
+    let pool = Pool::builder().build(manager).await?;
+
+    let conn = pool.get().await?;
+
+    // Keyed on (guid, sink) rather than guid alone: delivery is per-sink (one
+    // row per Discord channel / Telegram chat), so a sink that only just
+    // subscribed still gets a story that already went out elsewhere.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS posted_articles (
+            guid TEXT NOT NULL,
+            sink TEXT NOT NULL,
+            link TEXT NOT NULL,
+            posted_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            PRIMARY KEY (guid, sink)
+        )",
+        &[],
+    )
+    .await?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS feed_subscriptions (
+            channel_id BIGINT NOT NULL,
+            feed_url TEXT NOT NULL,
+            PRIMARY KEY (channel_id, feed_url)
+        )",
+        &[],
+    )
+    .await?;
+
+    // Tracks whether a feed's first-ever tick has already seeded its current
+    // window as posted, so a cold start doesn't replay the whole historical
+    // window as a delivery burst.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS seeded_feeds (
+            feed_url TEXT PRIMARY KEY
+        )",
+        &[],
+    )
+    .await?;
+
+    Ok(pool)
+}
+
+/// Stable identifier for an RSS item: its GUID when present, otherwise a hash
+/// of its link so items without a GUID can still be deduped.
+pub fn article_guid(item: &rss::Item) -> String {
+    if let Some(guid) = item.guid() {
+        return guid.value().to_string();
+    }
+
+    let mut hasher = DefaultHasher::new();
+    item.link().unwrap_or_default().hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
+/// Whether an article has already been posted to a given sink (e.g.
+/// `discord:<channel_id>` or `telegram:<chat_id>`).
+pub async fn is_posted(pool: &DbPool, guid: &str, sink: &str) -> Result<bool, Error> {
+    let conn = pool.get().await?;
+    let row = conn
+        .query_opt(
+            "SELECT 1 FROM posted_articles WHERE guid = $1 AND sink = $2",
+            &[&guid, &sink],
+        )
+        .await?;
+
+    Ok(row.is_some())
+}
+
+/// Record an article as posted to a sink so future ticks skip it there.
+pub async fn mark_posted(pool: &DbPool, guid: &str, sink: &str, link: &str) -> Result<(), Error> {
+    let conn = pool.get().await?;
+    conn.execute(
+        "INSERT INTO posted_articles (guid, sink, link) VALUES ($1, $2, $3)
+         ON CONFLICT (guid, sink) DO NOTHING",
+        &[&guid, &sink, &link],
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Subscribe a channel to a feed.
+pub async fn subscribe(pool: &DbPool, channel_id: u64, feed_url: &str) -> Result<(), Error> {
+    let conn = pool.get().await?;
+    conn.execute(
+        "INSERT INTO feed_subscriptions (channel_id, feed_url) VALUES ($1, $2)
+         ON CONFLICT (channel_id, feed_url) DO NOTHING",
+        &[&(channel_id as i64), &feed_url],
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Unsubscribe a channel from a feed.
+pub async fn unsubscribe(pool: &DbPool, channel_id: u64, feed_url: &str) -> Result<(), Error> {
+    let conn = pool.get().await?;
+    conn.execute(
+        "DELETE FROM feed_subscriptions WHERE channel_id = $1 AND feed_url = $2",
+        &[&(channel_id as i64), &feed_url],
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// The feeds a channel is subscribed to.
+pub async fn list_subscriptions(pool: &DbPool, channel_id: u64) -> Result<Vec<String>, Error> {
+    let conn = pool.get().await?;
+    let rows = conn
+        .query(
+            "SELECT feed_url FROM feed_subscriptions WHERE channel_id = $1 ORDER BY feed_url",
+            &[&(channel_id as i64)],
+        )
+        .await?;
+
+    Ok(rows.iter().map(|row| row.get("feed_url")).collect())
+}
+
+/// The channels subscribed to a given feed, consulted each tick so the
+/// scheduler isn't limited to the `feeds.toml` feed registry.
+pub async fn channels_for_feed(pool: &DbPool, feed_url: &str) -> Result<Vec<u64>, Error> {
+    let conn = pool.get().await?;
+    let rows = conn
+        .query(
+            "SELECT channel_id FROM feed_subscriptions WHERE feed_url = $1",
+            &[&feed_url],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| row.get::<_, i64>("channel_id") as u64)
+        .collect())
+}
+
+/// Every distinct feed URL with at least one subscriber, re-read on each
+/// discovery tick so a `/feed subscribe` for a URL outside the `feeds.toml`
+/// registry still gets a polling job.
+pub async fn distinct_feed_urls(pool: &DbPool) -> Result<Vec<String>, Error> {
+    let conn = pool.get().await?;
+    let rows = conn
+        .query("SELECT DISTINCT feed_url FROM feed_subscriptions", &[])
+        .await?;
+
+    Ok(rows.iter().map(|row| row.get("feed_url")).collect())
+}
+
+/// Whether a feed's current window has already been seeded as posted (see
+/// `mark_feed_seeded`).
+pub async fn is_feed_seeded(pool: &DbPool, feed_url: &str) -> Result<bool, Error> {
+    let conn = pool.get().await?;
+    let row = conn
+        .query_opt(
+            "SELECT 1 FROM seeded_feeds WHERE feed_url = $1",
+            &[&feed_url],
+        )
+        .await?;
+
+    Ok(row.is_some())
+}
+
+/// Record that a feed's first tick has been seeded, so it's never treated as
+/// a cold start again.
+pub async fn mark_feed_seeded(pool: &DbPool, feed_url: &str) -> Result<(), Error> {
+    let conn = pool.get().await?;
+    conn.execute(
+        "INSERT INTO seeded_feeds (feed_url) VALUES ($1) ON CONFLICT (feed_url) DO NOTHING",
+        &[&feed_url],
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rss::{GuidBuilder, ItemBuilder};
+
+    #[test]
+    fn article_guid_prefers_explicit_guid() {
+        let item = ItemBuilder::default()
+            .guid(Some(GuidBuilder::default().value("abc-123").build()))
+            .link(Some("https://example.com/a".to_string()))
+            .build();
+
+        assert_eq!(article_guid(&item), "abc-123");
+    }
+
+    #[test]
+    fn article_guid_falls_back_to_link_hash_when_guid_missing() {
+        let with_link_a = ItemBuilder::default()
+            .link(Some("https://example.com/a".to_string()))
+            .build();
+        let with_link_b = ItemBuilder::default()
+            .link(Some("https://example.com/b".to_string()))
+            .build();
+
+        assert_ne!(article_guid(&with_link_a), article_guid(&with_link_b));
+    }
+
+    #[test]
+    fn article_guid_is_stable_for_items_missing_both_guid_and_link() {
+        let a = ItemBuilder::default().build();
+        let b = ItemBuilder::default().build();
+
+        assert_eq!(article_guid(&a), article_guid(&b));
+    }
+}
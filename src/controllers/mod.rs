@@ -0,0 +1,3 @@
+pub mod database;
+pub mod notify;
+pub mod telegram;
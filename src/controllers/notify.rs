@@ -0,0 +1,45 @@
+use poise::serenity_prelude::{ChannelId, Context};
+
+use crate::Error;
+
+const STATUS_COLOR: u32 = 0x2ecc71;
+const ERROR_COLOR: u32 = 0xe74c3c;
+
+/// Send a status/error embed to a Discord channel, used to surface
+/// operational events (bot ready, command failures, feed-fetch errors)
+/// without tailing stdout.
+async fn send_embed(
+    ctx: &Context,
+    channel: ChannelId,
+    title: &str,
+    description: &str,
+    color: u32,
+) -> Result<(), Error> {
+    channel
+        .send_message(ctx, |m| {
+            m.embed(|e| e.title(title).description(description).color(color))
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Send an informational status embed, e.g. "bot ready".
+pub async fn notify_status(
+    ctx: &Context,
+    channel: ChannelId,
+    title: &str,
+    description: &str,
+) -> Result<(), Error> {
+    send_embed(ctx, channel, title, description, STATUS_COLOR).await
+}
+
+/// Send an error embed, e.g. a command failure or feed-fetch error.
+pub async fn notify_error(
+    ctx: &Context,
+    channel: ChannelId,
+    title: &str,
+    description: &str,
+) -> Result<(), Error> {
+    send_embed(ctx, channel, title, description, ERROR_COLOR).await
+}
@@ -0,0 +1,36 @@
+use teloxide::{prelude::*, types::ChatId};
+
+use crate::Error;
+
+/// Thin wrapper around a `teloxide` bot bound to a single destination chat.
+pub struct TelegramClient {
+    bot: Bot,
+    chat_id: ChatId,
+}
+
+impl TelegramClient {
+    pub fn new(token: &str, chat_id: i64) -> Self {
+        Self {
+            bot: Bot::new(token),
+            chat_id: ChatId(chat_id),
+        }
+    }
+
+    /// The chat this client delivers to, used as part of a sink's dedup key.
+    pub fn chat_id(&self) -> i64 {
+        self.chat_id.0
+    }
+
+    /// Send a plain-text mirror of an RSS item: title and link.
+    pub async fn send_story(&self, item: &rss::Item) -> Result<(), Error> {
+        let text = format!(
+            "{}\n{}",
+            item.title().unwrap_or("Untitled"),
+            item.link().unwrap_or("")
+        );
+
+        self.bot.send_message(self.chat_id, text).await?;
+
+        Ok(())
+    }
+}
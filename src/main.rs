@@ -1,10 +1,17 @@
 #![warn(clippy::str_to_string)]
 
 mod commands;
+mod config;
+mod controllers;
+mod sinks;
 
 use ::rss::Channel;
+use config::FeedConfig;
+use controllers::{database::DbPool, telegram::TelegramClient};
 use poise::serenity_prelude::{self as serenity};
-use std::{env::var, sync::Arc, time::Duration};
+use sinks::{DiscordSink, NewsSink, TelegramSink};
+use std::{collections::HashSet, env::var, sync::Arc, time::Duration};
+use tokio::sync::Mutex;
 use tokio_cron_scheduler::{Job, JobScheduler};
 
 // Types used by all command functions
@@ -12,8 +19,11 @@ type Error = Box<dyn std::error::Error + Send + Sync>;
 type Context<'a> = poise::Context<'a, Data, Error>;
 
 // Custom user data passed to all command functions
-pub struct Data {}
+pub struct Data {
+    db: Arc<DbPool>,
+}
 
+#[tracing::instrument(skip_all)]
 async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
     // This is our custom error handler
     // They are many errors that can occur, so we only handle the ones we want to customize
@@ -21,24 +31,357 @@ async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
     match error {
         poise::FrameworkError::Setup { error, .. } => panic!("Failed to start bot: {:?}", error),
         poise::FrameworkError::Command { error, ctx } => {
-            println!("Error in command `{}`: {:?}", ctx.command().name, error,);
+            tracing::error!(command = %ctx.command().qualified_name, %error, "command failed");
+
+            if let Some(channel) = *config::NOTIFY_CHANNEL_ID {
+                let description = format!("`{}`: {}", ctx.command().qualified_name, error);
+                let notified = controllers::notify::notify_error(
+                    ctx.serenity_context(),
+                    channel,
+                    "Command failed",
+                    &description,
+                )
+                .await;
+
+                if let Err(e) = notified {
+                    tracing::error!("Failed to send command failure notice: {}", e);
+                }
+            }
         }
         error => {
             if let Err(e) = poise::builtins::on_error(error).await {
-                println!("Error while handling error: {}", e)
+                tracing::error!("Error while handling error: {}", e);
+            }
+        }
+    }
+}
+
+/// Fetch and parse a feed, returning an error instead of panicking so one bad
+/// response can't take down the repeating job.
+async fn fetch_feed(url: &str) -> Result<Channel, Error> {
+    let bytes = reqwest::get(url).await?.bytes().await?;
+    let channel = Channel::read_from(&bytes[..])?;
+
+    Ok(channel)
+}
+
+/// Notify the configured channel that a feed fetch failed, if one is set.
+async fn notify_feed_error(ctx: &serenity::Context, feed_url: &str, message: &str) {
+    let Some(channel) = *config::NOTIFY_CHANNEL_ID else {
+        return;
+    };
+
+    let description = format!("`{}`: {}", feed_url, message);
+    if let Err(e) =
+        controllers::notify::notify_error(ctx, channel, "Feed fetch failed", &description).await
+    {
+        tracing::error!("Failed to send feed failure notice: {}", e);
+    }
+}
+
+/// Post a single RSS item to every sink subscribed to its feed, checking and
+/// recording delivery per sink (rather than once globally) so a sink that
+/// just subscribed still receives stories already delivered elsewhere, and a
+/// sink whose send fails is retried on the next tick instead of being marked
+/// posted regardless of outcome.
+#[tracing::instrument(skip(story, sinks, db), fields(guid = %guid))]
+async fn deliver_story(story: &rss::Item, guid: &str, sinks: &[Box<dyn NewsSink>], db: &DbPool) {
+    let Some(story_link) = story.link.clone() else {
+        return;
+    };
+
+    for sink in sinks {
+        let sink_key = sink.key();
+
+        match controllers::database::is_posted(db, guid, &sink_key).await {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::error!(sink = %sink_key, "Error checking posted_articles: {}", e);
+                continue;
+            }
+        }
+
+        if let Err(e) = sink.post(story).await {
+            tracing::error!(sink = %sink_key, "Error posting story: {}", e);
+            continue;
+        }
+
+        if let Err(e) = controllers::database::mark_posted(db, guid, &sink_key, &story_link).await
+        {
+            tracing::error!(sink = %sink_key, "Error recording posted article: {}", e);
+        }
+
+        tracing::info!(sink = %sink_key, "Posted story");
+    }
+}
+
+/// Build the sinks a feed's items should be delivered to: every channel
+/// currently subscribed to `feed_url`, plus `primary_channel` (the channel
+/// configured in the `feeds.toml` registry, if any), plus the shared
+/// Telegram mirror, if configured. Re-queried every tick so admins can
+/// add/remove destinations without a redeploy.
+async fn sinks_for_feed(
+    feed_url: &str,
+    primary_channel: Option<serenity::ChannelId>,
+    accent_color: u32,
+    ctx: &Arc<serenity::Context>,
+    db: &DbPool,
+    telegram: &Option<Arc<TelegramClient>>,
+) -> Vec<Box<dyn NewsSink>> {
+    let mut destinations: Vec<serenity::ChannelId> =
+        match controllers::database::channels_for_feed(db, feed_url).await {
+            Ok(channels) => channels.into_iter().map(serenity::ChannelId).collect(),
+            Err(e) => {
+                tracing::error!("Error loading subscriptions for `{}`: {}", feed_url, e);
+                Vec::new()
             }
+        };
+    if let Some(channel_id) = primary_channel {
+        if !destinations.contains(&channel_id) {
+            destinations.push(channel_id);
+        }
+    }
+
+    let mut sinks: Vec<Box<dyn NewsSink>> = destinations
+        .into_iter()
+        .map(|channel_id| {
+            Box::new(DiscordSink {
+                ctx: Arc::clone(ctx),
+                channel_id,
+                accent_color,
+            }) as Box<dyn NewsSink>
+        })
+        .collect();
+    if let Some(client) = telegram {
+        sinks.push(Box::new(TelegramSink {
+            client: Arc::clone(client),
+        }));
+    }
+
+    sinks
+}
+
+/// One tick of a feed's repeating job: fetch, then deliver every new item in
+/// chronological order to every subscribed sink. On the feed's first-ever
+/// tick, the current window is seeded as already posted instead of
+/// delivered, so a cold start doesn't replay the whole historical window as
+/// a delivery burst. The seed covers only the sinks known at that tick; a
+/// sink added later is sent every item in the window it hasn't received yet.
+#[tracing::instrument(skip(ctx, db, telegram), fields(feed_url = %feed_url))]
+async fn poll_feed(
+    feed_url: String,
+    primary_channel: Option<serenity::ChannelId>,
+    accent_color: u32,
+    ctx: Arc<serenity::Context>,
+    db: Arc<DbPool>,
+    telegram: Option<Arc<TelegramClient>>,
+) {
+    let content_channel = match fetch_feed(&feed_url).await {
+        Ok(channel) => channel,
+        Err(e) => {
+            tracing::error!("Failed to fetch/parse feed: {}", e);
+            notify_feed_error(&ctx, &feed_url, &e.to_string()).await;
+            return;
+        }
+    };
+
+    let sinks =
+        sinks_for_feed(&feed_url, primary_channel, accent_color, &ctx, &db, &telegram).await;
+
+    match controllers::database::is_feed_seeded(&db, &feed_url).await {
+        Ok(true) => {}
+        Ok(false) => {
+            seed_feed(&content_channel, &feed_url, &sinks, &db).await;
+            return;
+        }
+        Err(e) => {
+            tracing::error!("Error checking feed seed state: {}", e);
+            return;
         }
     }
+
+    // Items come back newest-first; post in chronological order so a
+    // burst of new stories arrives in the order they were published.
+    for story in content_channel.items.iter().rev() {
+        let guid = controllers::database::article_guid(story);
+        deliver_story(story, &guid, &sinks, &db).await;
+    }
+}
+
+/// Mark every item currently in a feed's window as already posted, to every
+/// sink known at this moment, without delivering any of them. Runs once, on
+/// a feed's first-ever tick, so neither a cold start nor a brand-new
+/// `/feed subscribe` feed floods its sinks with its entire historical
+/// window.
+async fn seed_feed(
+    content_channel: &Channel,
+    feed_url: &str,
+    sinks: &[Box<dyn NewsSink>],
+    db: &DbPool,
+) {
+    for story in &content_channel.items {
+        let guid = controllers::database::article_guid(story);
+        let Some(link) = story.link.clone() else {
+            continue;
+        };
+
+        for sink in sinks {
+            let sink_key = sink.key();
+            if let Err(e) = controllers::database::mark_posted(db, &guid, &sink_key, &link).await
+            {
+                tracing::error!(
+                    feed_url = %feed_url,
+                    sink = %sink_key,
+                    "Error seeding posted_articles: {}",
+                    e
+                );
+            }
+        }
+    }
+
+    if let Err(e) = controllers::database::mark_feed_seeded(db, feed_url).await {
+        tracing::error!(feed_url = %feed_url, "Error marking feed as seeded: {}", e);
+    }
+}
+
+/// Build the repeating job that polls a feed from the `feeds.toml` registry
+/// and posts any new stories to its configured channel (and any channels
+/// that have since subscribed).
+fn build_feed_job(
+    feed: FeedConfig,
+    ctx: Arc<serenity::Context>,
+    db: Arc<DbPool>,
+    telegram: Option<Arc<TelegramClient>>,
+) -> Job {
+    Job::new_repeated_async(Duration::from_secs(feed.interval_secs), move |_uuid, _l| {
+        Box::pin(poll_feed(
+            feed.url.clone(),
+            Some(feed.channel()),
+            feed.accent_color,
+            Arc::clone(&ctx),
+            Arc::clone(&db),
+            telegram.clone(),
+        ))
+    })
+    .unwrap()
+}
+
+/// Poll a feed that has no `feeds.toml` registry entry — one that was only
+/// ever added via `/feed subscribe`. Spawned directly (rather than registered
+/// with the cron scheduler) since these are discovered at runtime, on the
+/// registry's default cadence and accent color. Stops itself once the last
+/// subscribed channel unsubscribes, forgetting the URL so a later
+/// `/feed subscribe` is picked up as a fresh discovery.
+fn spawn_discovered_feed(
+    feed_url: String,
+    ctx: Arc<serenity::Context>,
+    db: Arc<DbPool>,
+    telegram: Option<Arc<TelegramClient>>,
+    known_feed_urls: Arc<Mutex<HashSet<String>>>,
+) {
+    tokio::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(config::DEFAULT_POLL_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+
+            match controllers::database::channels_for_feed(&db, &feed_url).await {
+                Ok(channels) if channels.is_empty() => {
+                    tracing::info!(feed_url = %feed_url, "no subscribers left, stopping poller");
+                    known_feed_urls.lock().await.remove(&feed_url);
+                    return;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(feed_url = %feed_url, "Error loading subscriptions: {}", e);
+                    continue;
+                }
+            }
+
+            poll_feed(
+                feed_url.clone(),
+                None,
+                config::DEFAULT_ACCENT_COLOR,
+                Arc::clone(&ctx),
+                Arc::clone(&db),
+                telegram.clone(),
+            )
+            .await;
+        }
+    });
+}
+
+/// Build the discovery job: on each tick, re-read the distinct feed URLs
+/// with at least one subscriber and spawn a poller for any that aren't
+/// already covered by the `feeds.toml` registry or a previous discovery.
+/// This is what lets `/feed subscribe <url>` work for a URL outside
+/// `feeds.toml` without a redeploy.
+fn build_discovery_job(
+    ctx: Arc<serenity::Context>,
+    db: Arc<DbPool>,
+    telegram: Option<Arc<TelegramClient>>,
+    known_feed_urls: Arc<Mutex<HashSet<String>>>,
+) -> Job {
+    Job::new_repeated_async(
+        Duration::from_secs(config::DEFAULT_POLL_INTERVAL_SECS),
+        move |_uuid, _l| {
+            let ctx = Arc::clone(&ctx);
+            let db = Arc::clone(&db);
+            let telegram = telegram.clone();
+            let known_feed_urls = Arc::clone(&known_feed_urls);
+
+            Box::pin(async move {
+                let subscribed = match controllers::database::distinct_feed_urls(&db).await {
+                    Ok(urls) => urls,
+                    Err(e) => {
+                        tracing::error!("Error loading feed subscriptions for discovery: {}", e);
+                        return;
+                    }
+                };
+
+                let mut known = known_feed_urls.lock().await;
+                for feed_url in subscribed {
+                    if known.insert(feed_url.clone()) {
+                        tracing::info!(
+                            feed_url = %feed_url,
+                            "discovered subscribed feed outside registry, scheduling poll"
+                        );
+                        spawn_discovered_feed(
+                            feed_url,
+                            Arc::clone(&ctx),
+                            Arc::clone(&db),
+                            telegram.clone(),
+                            Arc::clone(&known_feed_urls),
+                        );
+                    }
+                }
+            })
+        },
+    )
+    .unwrap()
+}
+
+/// Logged before every command invocation.
+#[tracing::instrument(skip(ctx), fields(command = %ctx.command().qualified_name))]
+async fn log_pre_command(ctx: Context<'_>) {
+    tracing::info!("executing command");
+}
+
+/// Logged after a command invocation completes successfully.
+#[tracing::instrument(skip(ctx), fields(command = %ctx.command().qualified_name))]
+async fn log_post_command(ctx: Context<'_>) {
+    tracing::info!("command executed");
 }
 
 #[tokio::main]
 async fn main() {
-    env_logger::init();
+    tracing_subscriber::fmt::init();
 
     // FrameworkOptions contains all of poise's configuration option in one struct
     // Every option can be omitted to use its default value
     let options = poise::FrameworkOptions {
-        commands: vec![commands::help::help()],
+        commands: vec![commands::help::help(), commands::news::feed()],
         prefix_options: poise::PrefixFrameworkOptions {
             prefix: Some("~".into()),
             edit_tracker: Some(poise::EditTracker::for_timespan(Duration::from_secs(3600))),
@@ -51,23 +394,45 @@ async fn main() {
         /// The global error handler for all error cases that may occur
         on_error: |error| Box::pin(on_error(error)),
         /// This code is run before every command
-        pre_command: |ctx| {
-            Box::pin(async move {
-                println!("Executing command {}...", ctx.command().qualified_name);
-            })
-        },
+        pre_command: |ctx| Box::pin(log_pre_command(ctx)),
         /// This code is run after a command if it was successful (returned Ok)
-        post_command: |ctx| {
-            Box::pin(async move {
-                println!("Executed command {}!", ctx.command().qualified_name);
-            })
-        },
+        post_command: |ctx| Box::pin(log_post_command(ctx)),
         /// Every command invocation must pass this check to continue execution
         command_check: Some(|ctx| {
             Box::pin(async move {
                 if ctx.author().id == 123456789 {
                     return Ok(false);
                 }
+
+                // Subscribing/unsubscribing changes which channels a feed posts to,
+                // so require Manage Channels the same way creating a webhook would.
+                let mutating_commands = ["subscribe", "unsubscribe"];
+                if mutating_commands.contains(&ctx.command().name.as_str()) {
+                    let Some(member) = ctx.author_member().await else {
+                        return Ok(false);
+                    };
+
+                    // `Member::permissions` only reflects guild-base roles, not
+                    // per-channel overwrites, but the gate is about this specific
+                    // channel, so check the effective permission there instead.
+                    // Read from the cache rather than `to_channel`'s REST
+                    // fallback, so a transient API hiccup can't turn this check
+                    // into a command failure.
+                    let Some(guild) = ctx.guild() else {
+                        return Ok(false);
+                    };
+                    let Some(guild_channel) = guild.channels.get(&ctx.channel_id()) else {
+                        return Ok(false);
+                    };
+
+                    if !guild_channel
+                        .permissions_for_user(ctx, member.user.id)?
+                        .manage_channels()
+                    {
+                        return Ok(false);
+                    }
+                }
+
                 Ok(true)
             })
         }),
@@ -76,7 +441,7 @@ async fn main() {
         skip_checks_for_owners: false,
         event_handler: |_ctx, event, _framework, _data| {
             Box::pin(async move {
-                println!("Got an event in event handler: {:?}", event.name());
+                tracing::debug!(event = event.name(), "got an event");
                 Ok(())
             })
         },
@@ -92,57 +457,65 @@ async fn main() {
             Box::pin(async move {
                 let shared_ctx = Arc::new(ctx.clone());
 
-                println!("Logged in as {}", _ready.user.name);
+                tracing::info!(user = %_ready.user.name, "logged in");
                 poise::builtins::register_globally(ctx, &framework.options().commands).await?;
 
-                let sched = JobScheduler::new().await?;
-                let ctx_clone = Arc::clone(&shared_ctx); // Clone the Arc for use inside the closure
-
-                let news_update_job =
-                    Job::new_repeated_async(Duration::from_secs(60), move |_uuid, _l| {
-                        let ctx = Arc::clone(&ctx_clone); // Clone again inside the closure
-
-                        Box::pin(async move {
-                            let content =
-                                reqwest::get("https://www.dlnews.com/arc/outboundfeeds/rss/")
-                                    .await
-                                    .unwrap()
-                                    .bytes()
-                                    .await
-                                    .unwrap();
+                if let Some(channel) = *config::NOTIFY_CHANNEL_ID {
+                    let notified = controllers::notify::notify_status(
+                        ctx,
+                        channel,
+                        "Bot ready",
+                        &format!("Logged in as {}", _ready.user.name),
+                    )
+                    .await;
 
-                            let content_channel = Channel::read_from(&content[..]).unwrap();
-
-                            let story = content_channel.items[0].clone();
-
-                            let story_link = story.link.unwrap();
+                    if let Err(e) = notified {
+                        tracing::error!("Failed to send ready notice: {}", e);
+                    }
+                }
 
-                            let channel_id = serenity::ChannelId(1143749967706603602);
+                let database_url =
+                    var("DATABASE_URL").expect("Missing `DATABASE_URL` env var, see README.");
+                let db = Arc::new(controllers::database::connect(&database_url).await?);
 
-                            let prev_news = channel_id
-                                .messages(&ctx, |retriever| retriever.limit(1))
-                                .await
-                                .unwrap();
-                            let prev_news = prev_news.get(0).unwrap();
+                // Telegram mirroring is optional: only wire it up if both env vars
+                // are set, so running without a Telegram bot still works.
+                let telegram = match (var("TELEGRAM_TOKEN"), var("TELEGRAM_CHAT_ID")) {
+                    (Ok(token), Ok(chat_id)) => {
+                        let chat_id: i64 = chat_id
+                            .parse()
+                            .expect("TELEGRAM_CHAT_ID must be an integer chat id");
+                        Some(Arc::new(TelegramClient::new(&token, chat_id)))
+                    }
+                    _ => None,
+                };
 
-                            if prev_news.content == story_link {
-                                println!("No new news");
-                                return;
-                            }
+                let sched = JobScheduler::new().await?;
+                let known_feed_urls: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(
+                    config::FEEDS.iter().map(|feed| feed.url.clone()).collect(),
+                ));
 
-                            match channel_id.say(ctx, story_link).await {
-                                Ok(_) => println!("Posted news"),
-                                Err(e) => println!("Error posting news: {}", e),
-                            };
-                        })
-                    })
-                    .unwrap();
+                for feed in config::FEEDS.iter() {
+                    let job = build_feed_job(
+                        feed.clone(),
+                        Arc::clone(&shared_ctx),
+                        Arc::clone(&db),
+                        telegram.clone(),
+                    );
+                    sched.add(job).await?;
+                }
 
-                sched.add(news_update_job).await?;
+                let discovery_job = build_discovery_job(
+                    Arc::clone(&shared_ctx),
+                    Arc::clone(&db),
+                    telegram.clone(),
+                    Arc::clone(&known_feed_urls),
+                );
+                sched.add(discovery_job).await?;
 
                 sched.start().await?;
 
-                Ok(Data {})
+                Ok(Data { db })
             })
         })
         .options(options)
@@ -152,7 +525,7 @@ async fn main() {
         .run()
         .await
         .map_err(|e| {
-            println!("Failed to start bot: {}", e);
+            tracing::error!("Failed to start bot: {}", e);
             e
         })
         .unwrap();
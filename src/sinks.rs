@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use poise::serenity_prelude::{ChannelId, Context as SerenityContext};
+
+use crate::{commands, controllers::telegram::TelegramClient, Error};
+
+/// A destination a newly-detected RSS item can be delivered to. Keeping the
+/// polling/dedup logic platform-agnostic behind this trait means a new
+/// delivery platform is a new impl, not a change to the scheduler.
+#[poise::async_trait]
+pub trait NewsSink: Send + Sync {
+    /// Stable identifier for this sink (e.g. `discord:<channel_id>`), used as
+    /// half of the dedup key so the same story can be delivered to multiple
+    /// sinks independently and retried per-sink on failure.
+    fn key(&self) -> String;
+
+    async fn post(&self, item: &rss::Item) -> Result<(), Error>;
+}
+
+/// Posts a story as a rich embed to a Discord channel.
+pub struct DiscordSink {
+    pub ctx: Arc<SerenityContext>,
+    pub channel_id: ChannelId,
+    pub accent_color: u32,
+}
+
+#[poise::async_trait]
+impl NewsSink for DiscordSink {
+    fn key(&self) -> String {
+        format!("discord:{}", self.channel_id.0)
+    }
+
+    async fn post(&self, item: &rss::Item) -> Result<(), Error> {
+        let embed = commands::news::build_story_embed(item, self.accent_color);
+        self.channel_id
+            .send_message(&self.ctx, |m| m.set_embed(embed))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Mirrors a story to a Telegram chat.
+pub struct TelegramSink {
+    pub client: Arc<TelegramClient>,
+}
+
+#[poise::async_trait]
+impl NewsSink for TelegramSink {
+    fn key(&self) -> String {
+        format!("telegram:{}", self.client.chat_id())
+    }
+
+    async fn post(&self, item: &rss::Item) -> Result<(), Error> {
+        self.client.send_story(item).await
+    }
+}